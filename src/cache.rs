@@ -0,0 +1,259 @@
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+    io::{ErrorKind, Read, Write},
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{PluginHook, ShellState, ZulaError};
+
+///An entry in the on-disk plugin cache: enough information to tell whether a `.so` has changed
+///since it was last probed, plus the name it was last loaded under. Until the plugin named by
+///`name` is actually invoked, the corresponding [`PluginEntry::Pending`] slot holds one of these
+///instead of a loaded [`PluginHook`], so startup doesn't have to `dlopen` every plugin just to
+///learn its name.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct CachedEntry {
+    pub path: PathBuf,
+    pub name: String,
+    pub mtime: u64,
+    pub hash: u64,
+}
+
+///What `config.plugins` holds for a given name: either a fully loaded plugin (paired with the
+///signature it was loaded with, so writing the cache back out doesn't have to re-hash a file that
+///hasn't been touched since), or a cache record describing a plugin whose `.so` hasn't changed and
+///so hasn't been `dlopen`'d yet.
+pub(crate) enum PluginEntry {
+    Loaded(PluginHook, CachedEntry),
+    Pending(CachedEntry),
+}
+
+impl PluginEntry {
+    ///The path this entry was (or would be) loaded from, whether it's actually loaded or still
+    ///just a cache record.
+    pub(crate) fn path(&self) -> &Path {
+        match self {
+            Self::Loaded(_, signature) | Self::Pending(signature) => &signature.path,
+        }
+    }
+}
+
+impl CachedEntry {
+    pub(crate) fn for_path(name: String, path: PathBuf) -> Result<Self, ZulaError> {
+        let (mtime, hash) = signature(&path)?;
+        Ok(Self {
+            path,
+            name,
+            mtime,
+            hash,
+        })
+    }
+
+    ///Whether the file this entry describes still has the mtime and hash it was cached with.
+    fn is_fresh(&self) -> bool {
+        signature(&self.path)
+            .map(|(mtime, hash)| mtime == self.mtime && hash == self.hash)
+            .unwrap_or(false)
+    }
+}
+
+fn signature(path: &Path) -> Result<(u64, u64), ZulaError> {
+    let meta = std::fs::metadata(path)?;
+    let mtime = meta
+        .modified()?
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+
+    let bytes = std::fs::read(path)?;
+    let mut hasher = DefaultHasher::new();
+    bytes.hash(&mut hasher);
+
+    Ok((mtime, hasher.finish()))
+}
+
+///Reads and Brotli-decompresses `path`, then decodes a sequence of length-prefixed MessagePack
+///[`CachedEntry`] records. A record that fails to decode is reported in the returned error list but
+///does not stop the rest of the cache from being read. A missing file (e.g. a fresh install that
+///hasn't written a cache yet) is treated as an empty cache rather than an error.
+fn read_records(path: &Path) -> Result<(Vec<CachedEntry>, Vec<ZulaError>), ZulaError> {
+    let compressed = match std::fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok((Vec::new(), Vec::new())),
+        Err(e) => return Err(e.into()),
+    };
+    let mut raw = Vec::new();
+    brotli::Decompressor::new(&compressed[..], 4096).read_to_end(&mut raw)?;
+
+    let mut entries = Vec::new();
+    let mut errors = Vec::new();
+    let mut pos = 0;
+
+    while pos + 4 <= raw.len() {
+        let len = u32::from_le_bytes(raw[pos..pos + 4].try_into().unwrap()) as usize;
+        pos += 4;
+        if pos + len > raw.len() {
+            errors.push(ZulaError::CacheCorrupt);
+            break;
+        }
+
+        match rmp_serde::from_slice::<CachedEntry>(&raw[pos..pos + len]) {
+            Ok(entry) => entries.push(entry),
+            Err(e) => errors.push(ZulaError::from(e)),
+        }
+        pos += len;
+    }
+
+    Ok((entries, errors))
+}
+
+///Encodes `entries` as length-prefixed MessagePack records and Brotli-compresses the result into
+///`path`. The on-disk blob is rewritten in full on every call, since Brotli gives no way to patch
+///a compressed stream in place; what's incremental is building `entries` in the first place. A
+///plugin that's merely `Pending`, or already `Loaded` from an earlier call to
+///[`ShellState::load_plugin`], reuses the [`CachedEntry`] signature it was registered with instead
+///of re-hashing its file, so the hashing done for a write scales with how many plugins actually
+///changed (or were newly discovered), not with how many exist. Encoding and compression still
+///cover every entry.
+fn write_records(path: &Path, entries: impl Iterator<Item = CachedEntry>) -> Result<(), ZulaError> {
+    let mut raw = Vec::new();
+    for entry in entries {
+        let bytes = rmp_serde::to_vec(&entry).map_err(|e| ZulaError::Opaque(Box::new(e)))?;
+        raw.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+        raw.extend_from_slice(&bytes);
+    }
+
+    let mut compressed = Vec::new();
+    {
+        let mut writer = brotli::CompressorWriter::new(&mut compressed, 4096, 9, 22);
+        writer.write_all(&raw)?;
+    }
+    std::fs::write(path, compressed)?;
+    Ok(())
+}
+
+impl ShellState {
+    ///Reads the binary plugin cache at `path`. A missing file is treated as an empty cache, so a
+    ///fresh install isn't fatal. Plugins whose file still exists with an unchanged mtime/hash are
+    ///registered as [`PluginEntry::Pending`] and only actually `dlopen`'d the first time they're
+    ///looked up via [`ShellState::plugin_lookup`]; stale or missing-signature entries are loaded
+    ///immediately via [`ShellState::load_plugin`], and the cache is rewritten afterwards so the
+    ///corrected signatures don't have to be rediscovered on the next startup. A single corrupt
+    ///record is reported in the returned list rather than aborting the rest of the cache.
+    pub fn load_plugin_cache(&mut self, path: impl Into<PathBuf>) -> Result<Vec<ZulaError>, ZulaError> {
+        let path = path.into();
+        let (cached, mut errors) = read_records(&path)?;
+
+        let mut reloaded_stale = false;
+        for entry in cached {
+            if entry.is_fresh() {
+                self.config
+                    .plugins
+                    .insert(entry.name.clone(), PluginEntry::Pending(entry));
+            } else {
+                reloaded_stale = true;
+                if let Err(e) = self.load_plugin(&entry.path) {
+                    errors.push(e);
+                }
+            }
+        }
+
+        self.cache_path = Some(path);
+        if reloaded_stale {
+            self.write_plugin_cache()?;
+        }
+        Ok(errors)
+    }
+
+    ///Writes the current `config.plugins` out to the cache file passed to the last
+    ///[`ShellState::load_plugin_cache`] call. Does nothing if no cache has been loaded. Each
+    ///entry's signature comes from what it was registered with (see [`PluginEntry`]), not a fresh
+    ///re-hash of its file, so plugins that stay unchanged are never re-hashed here.
+    pub(crate) fn write_plugin_cache(&self) -> Result<(), ZulaError> {
+        let Some(cache_path) = &self.cache_path else {
+            return Ok(());
+        };
+
+        let entries = self.config.plugins.values().map(|slot| match slot {
+            PluginEntry::Pending(signature) => signature.clone(),
+            PluginEntry::Loaded(_, signature) => signature.clone(),
+        });
+
+        write_records(cache_path, entries)
+    }
+
+    ///Loads the plugin at `path` and adds it to the on-disk cache (if one was loaded via
+    ///[`ShellState::load_plugin_cache`]), so the next startup doesn't have to probe it again.
+    pub fn plugin_add(&mut self, path: impl AsRef<std::ffi::OsStr>) -> Result<String, ZulaError> {
+        let name = self.load_plugin(&path)?;
+        self.write_plugin_cache()?;
+        Ok(name)
+    }
+
+    ///Unloads the named plugin and removes it from the on-disk cache.
+    pub fn plugin_rm(&mut self, name: &str) -> Result<(), ZulaError> {
+        self.unload_plugin(name)?;
+        self.write_plugin_cache()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_roundtrip_and_skip_corrupt() {
+        let dir = std::env::temp_dir().join("zula_core_cache_test");
+        std::fs::create_dir_all(&dir).unwrap();
+        let cache_path = dir.join("plugins.msgpackz");
+
+        let good = CachedEntry {
+            path: dir.join("good.so"),
+            name: "good".to_owned(),
+            mtime: 1,
+            hash: 2,
+        };
+        write_records(&cache_path, std::iter::once(good.clone())).unwrap();
+
+        let (entries, errors) = read_records(&cache_path).unwrap();
+        assert!(errors.is_empty());
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].name, "good");
+
+        // Truncate the length-prefixed record so it claims more bytes than are present; the
+        // reader should report it and stop, rather than panicking or looping forever.
+        let mut compressed = std::fs::read(&cache_path).unwrap();
+        let mut raw = Vec::new();
+        brotli::Decompressor::new(&compressed[..], 4096)
+            .read_to_end(&mut raw)
+            .unwrap();
+        raw.truncate(raw.len() - 1);
+        compressed.clear();
+        {
+            let mut writer = brotli::CompressorWriter::new(&mut compressed, 4096, 9, 22);
+            writer.write_all(&raw).unwrap();
+        }
+        std::fs::write(&cache_path, &compressed).unwrap();
+
+        let (entries, errors) = read_records(&cache_path).unwrap();
+        assert!(entries.is_empty());
+        assert_eq!(errors.len(), 1);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn missing_cache_file_reads_as_empty() {
+        let dir = std::env::temp_dir().join("zula_core_cache_test_missing");
+        std::fs::remove_dir_all(&dir).ok();
+        let cache_path = dir.join("plugins.msgpackz");
+
+        let (entries, errors) = read_records(&cache_path).unwrap();
+        assert!(entries.is_empty());
+        assert!(errors.is_empty());
+    }
+}
@@ -0,0 +1,113 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use serde::Deserialize;
+
+use crate::{cache::PluginEntry, ZulaError};
+
+///Holds configuration info.
+pub struct Config {
+    pub aliases: HashMap<String, String>,
+    pub hotkeys: HashMap<char, String>,
+    pub(crate) plugins: HashMap<String, PluginEntry>,
+    pub safety: bool,
+    ///The `[plugins]` table from the config file, if one was loaded. Consumed by
+    ///[`crate::ShellState::load_plugin_dir`].
+    pub plugin_dir: Option<PluginDirConfig>,
+}
+
+///The `[plugins]` table of a TOML config file: where to scan for plugins, which ones to skip or
+///allow, and the aliases to register for them.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PluginDirConfig {
+    ///Directory to scan for `.so` files.
+    pub path: PathBuf,
+    ///Plugin filenames to skip. Ignored when `as_whitelist` is set.
+    #[serde(default)]
+    pub blacklist: Vec<String>,
+    ///Plugin filenames to allow. Only consulted when `as_whitelist` is set.
+    #[serde(default)]
+    pub whitelist: Vec<String>,
+    ///When `true`, only plugins in `whitelist` are loaded; when `false` (the default), every
+    ///plugin not in `blacklist` is loaded.
+    #[serde(default)]
+    pub as_whitelist: bool,
+    ///Maps a plugin's `name()` to the alias it should be invoked as, e.g. `plugin.<alias>`
+    ///instead of `plugin.<name>`.
+    #[serde(default)]
+    pub alias: HashMap<String, String>,
+}
+
+///The raw shape of a zula TOML config file, before it's turned into a [`Config`].
+#[derive(Debug, Deserialize)]
+struct ConfigFile {
+    #[serde(default)]
+    aliases: HashMap<String, String>,
+    #[serde(default)]
+    hotkeys: HashMap<char, String>,
+    #[serde(default)]
+    safety: bool,
+    plugins: Option<PluginDirConfig>,
+}
+
+impl Config {
+    pub fn new() -> Self {
+        Self {
+            aliases: HashMap::new(),
+            hotkeys: HashMap::new(),
+            plugins: HashMap::new(),
+            safety: false,
+            plugin_dir: None,
+        }
+    }
+
+    ///Parses a TOML config file into a [`Config`]. Expects `aliases`, `hotkeys` and `safety` at
+    ///the top level, plus an optional `[plugins]` table (see [`PluginDirConfig`]).
+    pub fn from_file(path: impl Into<PathBuf>) -> Result<Self, ZulaError> {
+        let raw = std::fs::read_to_string(path.into())?;
+        let parsed: ConfigFile = toml::from_str(&raw)?;
+
+        Ok(Self {
+            aliases: parsed.aliases,
+            hotkeys: parsed.hotkeys,
+            plugins: HashMap::new(),
+            safety: parsed.safety,
+            plugin_dir: parsed.plugins,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_plugins_table() {
+        let toml = r#"
+            safety = true
+
+            [aliases]
+            ll = "ls -la"
+
+            [hotkeys]
+            a = "some command"
+
+            [plugins]
+            path = "/home/user/.config/zula/plugins"
+            as_whitelist = true
+            whitelist = ["my_plugin.so"]
+
+            [plugins.alias]
+            my_plugin = "mp"
+        "#;
+
+        let parsed: ConfigFile = toml::from_str(toml).unwrap();
+        assert!(parsed.safety);
+        assert_eq!(parsed.aliases.get("ll"), Some(&"ls -la".to_owned()));
+        assert_eq!(parsed.hotkeys.get(&'a'), Some(&"some command".to_owned()));
+
+        let plugins = parsed.plugins.unwrap();
+        assert!(plugins.as_whitelist);
+        assert_eq!(plugins.whitelist, vec!["my_plugin.so".to_owned()]);
+        assert_eq!(plugins.alias.get("my_plugin"), Some(&"mp".to_owned()));
+    }
+}
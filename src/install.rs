@@ -0,0 +1,190 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{ShellState, ZulaError};
+
+///One entry in the install record: where a plugin installed from a git url was cloned to, and
+///the alias (if any) it's invoked under.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Installed {
+    path: PathBuf,
+    alias: Option<String>,
+}
+
+///Tracks url -> clone location/alias for plugins installed via [`ShellState::install_plugin`],
+///so re-running install on a url already known updates the existing checkout in place instead of
+///cloning a duplicate.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct InstallRecord {
+    #[serde(default)]
+    installs: HashMap<String, Installed>,
+}
+
+impl InstallRecord {
+    fn record_path(base_dir: &Path) -> PathBuf {
+        base_dir.join("installed.toml")
+    }
+
+    fn load(base_dir: &Path) -> Result<Self, ZulaError> {
+        let path = Self::record_path(base_dir);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let raw = std::fs::read_to_string(path)?;
+        Ok(toml::from_str(&raw)?)
+    }
+
+    fn save(&self, base_dir: &Path) -> Result<(), ZulaError> {
+        let raw = toml::to_string_pretty(self).map_err(|e| ZulaError::Opaque(Box::new(e)))?;
+        std::fs::write(Self::record_path(base_dir), raw)?;
+        Ok(())
+    }
+}
+
+///Derives a filesystem-safe component name from a git url, e.g.
+///`https://github.com/user/my-plugin.git` -> `my-plugin`.
+fn component_name(url: &str) -> String {
+    url.trim_end_matches('/')
+        .trim_end_matches(".git")
+        .rsplit('/')
+        .next()
+        .filter(|s| !s.is_empty())
+        .unwrap_or(url)
+        .to_owned()
+}
+
+///Finds the single `.so` produced by `cargo build --release` in `repo_path`.
+fn find_release_lib(repo_path: &Path) -> Result<PathBuf, ZulaError> {
+    let release_dir = repo_path.join("target").join("release");
+    let mut candidates = std::fs::read_dir(&release_dir)?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().and_then(|e| e.to_str()) == Some("so"));
+
+    match (candidates.next(), candidates.next()) {
+        (Some(only), None) => Ok(only),
+        _ => Err(ZulaError::InstallFailed(format!(
+            "expected exactly one .so in {}",
+            release_dir.display()
+        ))),
+    }
+}
+
+impl ShellState {
+    ///Clones (or, if previously installed, fetches and updates) the git repository at `url` into
+    ///a managed directory under `config.plugin_dir`, builds it with `cargo build --release`, and
+    ///registers the resulting `.so` with [`ShellState::plugin_add`]. If a plugin cache was loaded
+    ///via [`ShellState::load_plugin_cache`], the plugin is written to it and gets picked up again
+    ///on the next startup; otherwise it is registered for the rest of this session only.
+    ///`alias`, if given, must match `[A-Za-z0-9_-]+` and lets the plugin be invoked as
+    ///`plugin.<alias>` instead of its internal `name()`. Re-running install on a url already
+    ///installed updates the existing checkout in place rather than cloning a duplicate.
+    pub fn install_plugin(
+        &mut self,
+        url: &str,
+        alias: Option<String>,
+    ) -> Result<String, ZulaError> {
+        if let Some(alias) = &alias {
+            if alias.is_empty()
+                || !alias
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+            {
+                return Err(ZulaError::InvalidAlias);
+            }
+        }
+
+        let base_dir = self
+            .config
+            .plugin_dir
+            .as_ref()
+            .ok_or(ZulaError::InvalidDir)?
+            .path
+            .clone();
+        let repos_dir = base_dir.join("repos");
+        std::fs::create_dir_all(&repos_dir)?;
+
+        let mut record = InstallRecord::load(&base_dir)?;
+        let repo_path = match record.installs.get(url) {
+            Some(installed) if installed.path.exists() => installed.path.clone(),
+            _ => repos_dir.join(component_name(url)),
+        };
+
+        if repo_path.join(".git").exists() {
+            let repo = repo_path.to_string_lossy().into_owned();
+            run_to_success(self, "git", &["-C", &repo, "fetch", "--all"])?;
+            run_to_success(self, "git", &["-C", &repo, "reset", "--hard", "FETCH_HEAD"])?;
+        } else {
+            let repo = repo_path.to_string_lossy().into_owned();
+            run_to_success(self, "git", &["clone", url, &repo])?;
+        }
+
+        let manifest = repo_path.join("Cargo.toml");
+        run_to_success(
+            self,
+            "cargo",
+            &[
+                "build",
+                "--release",
+                "--manifest-path",
+                &manifest.to_string_lossy(),
+            ],
+        )?;
+
+        let so_path = find_release_lib(&repo_path)?;
+        let name = self.plugin_add(&so_path)?;
+
+        if let Some(alias) = &alias {
+            self.config
+                .aliases
+                .insert(format!("plugin.{alias}"), format!("plugin.{name}"));
+        }
+
+        record.installs.insert(
+            url.to_owned(),
+            Installed {
+                path: repo_path,
+                alias,
+            },
+        );
+        record.save(&base_dir)?;
+
+        Ok(name)
+    }
+}
+
+///Runs `cmd` via [`ShellState::exec`] and turns a non-zero exit status into
+///[`ZulaError::InstallFailed`], since a failed `git`/`cargo` step should stop the install instead
+///of silently continuing with stale state.
+fn run_to_success(state: &mut ShellState, cmd: &str, args: &[&str]) -> Result<(), ZulaError> {
+    let status = state.exec(cmd, args)?;
+    if !status.success() {
+        return Err(ZulaError::InstallFailed(format!(
+            "`{cmd} {}` exited with {status}",
+            args.join(" ")
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn component_name_strips_git_suffix_and_path() {
+        assert_eq!(
+            component_name("https://github.com/user/my-plugin.git"),
+            "my-plugin"
+        );
+        assert_eq!(
+            component_name("git@github.com:user/my-plugin.git"),
+            "my-plugin"
+        );
+        assert_eq!(component_name("https://example.com/my-plugin/"), "my-plugin");
+    }
+}
@@ -14,49 +14,63 @@ crate-type = ["cdylib"]
 [dependencies]
 zula-core = "4.0.0"
 ```
-Import the [`Plugin`] trait and implement it on your plugin type.
+Import the [`Plugin`] trait and implement it on your plugin type, then use [`declare_plugin`] to
+generate the `init` export. This is the recommended entry point: it keeps you from having to
+hand-write `#[no_mangle] fn init`, which is easy to get wrong and silently breaks if the attribute
+is forgotten or the signature drifts.
 ```
-use zula_core::{Plugin, ShellState};
+use zula_core::{declare_plugin, Plugin, PluginContext};
 use std::error::Error;
 
 pub struct MyPlugin;
 
 impl Plugin for MyPlugin {
-    //since this function is called across abi boundaries, its important to include no_mangle so
-    //that rustc leaves the symbol as-is and can be called properly.
-    #[no_mangle]
     fn init(&self) -> Box<dyn Plugin> {
         Box::new(Self)
     }
     fn name(&self) -> &str {
         "my_plugin"
     }
-    fn call(&self, state: *mut ShellState) -> Result<(), Box<dyn Error>> {
-        println!("Hello, plugin!");
+    fn call(&self, ctx: &mut PluginContext) -> Result<(), Box<dyn Error + Send + Sync>> {
+        println!("Hello, plugin! cwd is {}", ctx.cwd());
         Ok(())
     }
 }
+
+declare_plugin!(MyPlugin, MyPlugin);
 ```
 Run `cargo build --release` to build your plugin. The library file should be in `target/release/lib<name>.so`. This is the file that you'll put in your plugins folder.
 
-Thats it! Run `zula cfg` inside zula to check that its loaded, and run `plugin.<name>` to use it. Due to weird ownership relationships, `call` has to take a raw pointer, so use it responsibly.
+Thats it! Run `zula cfg` inside zula to check that its loaded, and run `plugin.<name>` to use it. [`PluginContext`] exposes the safe surface of the shell state a plugin needs; [`ShellState::call_plugin`] builds it from a raw pointer at the call boundary so plugins never have to touch unsafe code themselves.
 "#]
 
 use std::{
-    collections::HashMap,
+    collections::HashSet,
     env,
     error::Error,
     ffi::OsStr,
     fmt::Display,
     io::{self, stdin, stdout, ErrorKind, Stdin, Stdout},
     ops::Deref,
-    process::Command,
+    os::unix::process::ExitStatusExt,
+    path::PathBuf,
+    process::{Command, ExitStatus},
 };
 
 use termion::raw::{IntoRawMode, RawTerminal};
 
 mod plug;
-pub use plug::{Plugin, PluginHook};
+pub use plug::{Plugin, PluginContext, PluginHook};
+
+mod run;
+
+mod config;
+pub use config::{Config, PluginDirConfig};
+
+mod cache;
+use cache::{CachedEntry, PluginEntry};
+
+mod install;
 
 #[repr(C)]
 ///The core shell state object. This api is WIP, and may become more locked down in the future.
@@ -68,25 +82,10 @@ pub struct ShellState {
 
     pub stdin: Stdin,
     pub stdout: RawTerminal<Stdout>,
-}
-///Holds configuration info.
-pub struct Config {
-    pub aliases: HashMap<String, String>,
-    pub hotkeys: HashMap<char, String>,
-    plugins: HashMap<String, PluginHook>,
-    pub safety: bool, 
-}
 
-
-impl Config {
-    pub fn new() -> Self {
-        Self {
-            aliases: HashMap::new(),
-            hotkeys: HashMap::new(),
-            plugins: HashMap::new(),
-            safety: false
-        }
-    }
+    ///The path last passed to [`ShellState::load_plugin_cache`], if any. Used by
+    ///[`ShellState::plugin_add`]/[`ShellState::plugin_rm`] to keep the cache file in sync.
+    cache_path: Option<PathBuf>,
 }
 
 impl ShellState {
@@ -109,6 +108,7 @@ impl ShellState {
 
             stdin: stdin(),
             stdout: stdout().into_raw_mode()?,
+            cache_path: None,
         })
     }
 
@@ -130,17 +130,22 @@ impl ShellState {
         head
     }
 
-    ///Execute a command. Does no proccessing such as aliases, chaining, and quoting.
+    ///Execute a command. Does no proccessing such as aliases, chaining, and quoting. Returns the
+    ///exit status of the spawned process so callers (such as [`ShellState::run`]) can implement
+    ///short-circuiting.
     pub fn exec(
         &mut self,
         cmd: impl AsRef<str>,
         args: &[impl AsRef<str>],
-    ) -> Result<(), ZulaError> {
+    ) -> Result<ExitStatus, ZulaError> {
         if cmd.as_ref() == "cd" {
-            match args.get(0) {
-                Some(targ) => return self.set_cwd(targ.as_ref()),
-                None => return Err(ZulaError::CommandEmpty),
-            }
+            return match args.get(0) {
+                Some(targ) => {
+                    self.set_cwd(targ.as_ref())?;
+                    Ok(ExitStatus::from_raw(0))
+                }
+                None => Err(ZulaError::CommandEmpty),
+            };
         }
 
         let mut exec = Command::new(cmd.as_ref());
@@ -158,26 +163,143 @@ impl ShellState {
             }
             Err(e) => { Err(Into::<ZulaError>::into(e)) }?,
         };
-        proc.wait()?;
-        Ok(())
+        Ok(proc.wait()?)
     }
-    ///Attempt to load a plugin from a path.
-    pub fn load_plugin(&mut self, path: impl AsRef<OsStr>) -> Result<(), libloading::Error> {
-        let plug = unsafe { PluginHook::new(path) }?;
-        self.config.plugins.insert(plug.name().to_owned(), plug);
-        Ok(())
-    }
-    ///Returns a hook to the given plugin if it exists.
-    pub fn plugin_lookup(&self, name: &str) -> Result<&PluginHook, ZulaError> {
+    ///Attempt to load a plugin from a path. Returns the plugin's name on success. Also computes
+    ///the plugin's signature (mtime + content hash) once here, so a later plugin-cache write
+    ///doesn't have to re-hash a file that hasn't changed since it was loaded.
+    pub fn load_plugin(&mut self, path: impl AsRef<OsStr>) -> Result<String, ZulaError> {
+        let plug = unsafe { PluginHook::new(path, self as *mut Self) }?;
+        let name = plug.name().to_owned();
+        let signature = CachedEntry::for_path(name.clone(), PathBuf::from(plug.path()))?;
         self.config
             .plugins
-            .get(name)
-            .ok_or(ZulaError::InvalidPlugin)
+            .insert(name.clone(), PluginEntry::Loaded(plug, signature));
+        Ok(name)
+    }
+    ///Walks the directory configured in `config.plugin_dir`, filters `.so` files by the
+    ///black/whitelist, and loads each survivor not already known (from a previous
+    ///[`ShellState::load_plugin_cache`] call or an earlier scan) with [`ShellState::load_plugin`],
+    ///then persists the cache if anything new was found. Returns the filename and error for each
+    ///plugin that failed to load, rather than aborting on the first bad library. Does nothing if
+    ///no `[plugins]` table was configured.
+    pub fn load_plugin_dir(&mut self) -> Result<Vec<(String, ZulaError)>, ZulaError> {
+        let Some(dir_cfg) = self.config.plugin_dir.clone() else {
+            return Ok(Vec::new());
+        };
+
+        let known_paths: HashSet<PathBuf> = self
+            .config
+            .plugins
+            .values()
+            .map(|entry| entry.path().to_path_buf())
+            .collect();
+
+        let mut failures = Vec::new();
+        let mut discovered_new = false;
+        for entry in std::fs::read_dir(&dir_cfg.path)? {
+            let path = entry?.path();
+            if path.extension().and_then(OsStr::to_str) != Some("so") {
+                continue;
+            }
+            let fname = path
+                .file_name()
+                .and_then(OsStr::to_str)
+                .unwrap_or_default()
+                .to_owned();
+
+            let allowed = if dir_cfg.as_whitelist {
+                dir_cfg.whitelist.iter().any(|w| w == &fname)
+            } else {
+                !dir_cfg.blacklist.iter().any(|b| b == &fname)
+            };
+            if !allowed || known_paths.contains(&path) {
+                continue;
+            }
+
+            match self.load_plugin(&path) {
+                Ok(name) => {
+                    discovered_new = true;
+                    if let Some(alias) = dir_cfg.alias.get(&name) {
+                        self.config
+                            .aliases
+                            .insert(format!("plugin.{alias}"), format!("plugin.{name}"));
+                    }
+                }
+                Err(e) => failures.push((fname, e)),
+            }
+        }
+
+        if discovered_new {
+            self.write_plugin_cache()?;
+        }
+
+        Ok(failures)
+    }
+    ///Unloads a plugin by name, calling its `unload` hook before dropping it. The pointer passed
+    ///to `unload` is taken from `self` here, right before the call, rather than one stashed at
+    ///load time: `ShellState` can move between loading a plugin and unloading it, which would
+    ///leave a stored pointer dangling. A plugin that's only cached (not yet `dlopen`'d) is simply
+    ///forgotten, since its `load` hook never ran.
+    pub fn unload_plugin(&mut self, name: &str) -> Result<(), ZulaError> {
+        let state: *mut Self = self;
+        let entry = self
+            .config
+            .plugins
+            .remove(name)
+            .ok_or(ZulaError::InvalidPlugin)?;
+        if let PluginEntry::Loaded(hook, _) = &entry {
+            hook.unload(state);
+        }
+        Ok(())
+    }
+    ///Returns a hook to the given plugin if it exists, `dlopen`-ing it first if it was only a
+    ///cached, unloaded entry.
+    pub fn plugin_lookup(&mut self, name: &str) -> Result<&PluginHook, ZulaError> {
+        if let Some(PluginEntry::Pending(entry)) = self.config.plugins.get(name) {
+            let path = entry.path.clone();
+            self.load_plugin(&path)?;
+        }
+
+        match self.config.plugins.get(name) {
+            Some(PluginEntry::Loaded(hook, _)) => Ok(hook),
+            _ => Err(ZulaError::InvalidPlugin),
+        }
     }
-    ///Returns an iterator over the currently loaded plugin names.
-    pub fn plugin_names(&self) -> std::collections::hash_map::Keys<'_, String, PluginHook> {
+    ///Returns an iterator over the currently known plugin names, loaded or merely cached.
+    pub fn plugin_names(&self) -> impl Iterator<Item = &String> {
         self.config.plugins.keys()
     }
+    ///Dispatches `plugin.<name>`: looks the plugin up (`dlopen`-ing it first if it was only a
+    ///cached entry), builds a [`PluginContext`] from `self` and calls [`Plugin::call`] with it.
+    ///This is the one place that actually performs the audited `PluginContext::new` deref, so
+    ///hosts never need to build a context by hand.
+    pub fn call_plugin(&mut self, name: &str) -> Result<(), ZulaError> {
+        self.plugin_lookup(name)?;
+
+        let state: *mut Self = self;
+        let hook: *const PluginHook = match self.config.plugins.get(name) {
+            Some(PluginEntry::Loaded(hook, _)) => hook,
+            _ => return Err(ZulaError::InvalidPlugin),
+        };
+
+        let mut ctx = unsafe { PluginContext::new(state) };
+        unsafe { (*hook).call(&mut ctx) }.map_err(ZulaError::from)
+    }
+}
+
+impl Drop for ShellState {
+    ///Drains every remaining plugin through [`ShellState::unload_plugin`] before the `plugins`
+    ///map is dropped, so [`Plugin::unload`] still runs on normal shutdown and not only when a
+    ///plugin is explicitly removed with `unload_plugin`/`plugin_rm`. Collects the names first
+    ///rather than draining the map in place, since `unload_plugin` needs `&mut self` to hand
+    ///plugins a fresh pointer, and that borrow can't overlap one already iterating `self.config.plugins`.
+    fn drop(&mut self) {
+        let names: Vec<String> = self.config.plugins.keys().cloned().collect();
+        for name in names {
+            let _ = self.unload_plugin(&name);
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -190,6 +312,11 @@ pub enum ZulaError {
     RecursiveAlias,
     InvalidPlugin,
     LibErr(libloading::Error),
+    TomlErr(toml::de::Error),
+    CacheDecodeErr(rmp_serde::decode::Error),
+    CacheCorrupt,
+    InvalidAlias,
+    InstallFailed(String),
     Opaque(Box<dyn Error + Send + Sync>),
 }
 
@@ -203,6 +330,16 @@ impl From<libloading::Error> for ZulaError {
         Self::LibErr(value)
     }
 }
+impl From<toml::de::Error> for ZulaError {
+    fn from(value: toml::de::Error) -> Self {
+        Self::TomlErr(value)
+    }
+}
+impl From<rmp_serde::decode::Error> for ZulaError {
+    fn from(value: rmp_serde::decode::Error) -> Self {
+        Self::CacheDecodeErr(value)
+    }
+}
 impl From<Box<dyn Error + Send + Sync>> for ZulaError {
     fn from(value: Box<(dyn std::error::Error + Send + Sync + 'static)>) -> Self {
         Self::Opaque(value)
@@ -221,6 +358,11 @@ impl Display for ZulaError {
             Self::RecursiveAlias => write!(f, "recursive alias called\r\n"),
             Self::InvalidPlugin => write!(f, "plugin not found\r\n"),
             Self::LibErr(e) => write!(f, "lib error: {e}\r\n"),
+            Self::TomlErr(e) => write!(f, "config error: {e}\r\n"),
+            Self::CacheDecodeErr(e) => write!(f, "plugin cache error: {e}\r\n"),
+            Self::CacheCorrupt => write!(f, "plugin cache is corrupt\r\n"),
+            Self::InvalidAlias => write!(f, "alias must match [A-Za-z0-9_-]+\r\n"),
+            Self::InstallFailed(msg) => write!(f, "plugin install failed: {msg}\r\n"),
             Self::Opaque(e) => write!(f, "external error: {e}\r\n"),
         }
     }
@@ -232,6 +374,8 @@ impl Error for ZulaError {
         match self {
             Self::Io(e) => Some(e),
             Self::LibErr(e) => Some(e),
+            Self::TomlErr(e) => Some(e),
+            Self::CacheDecodeErr(e) => Some(e),
             Self::Opaque(e) => Some(e.deref()),
             _ => None,
         }
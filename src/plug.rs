@@ -1,13 +1,17 @@
 use std::{
+    collections::HashMap,
     error::Error,
     ffi::OsStr,
-    ops::{Deref, DerefMut},
-    path::Path, mem::ManuallyDrop,
+    io::{Stdin, Stdout},
+    mem::ManuallyDrop,
+    ops::Deref,
+    process::ExitStatus,
 };
 
 use libloading::Library;
+use termion::raw::RawTerminal;
 
-use crate::ShellState;
+use crate::{ShellState, ZulaError};
 
 ///The plugin trait that defines how a plugin object acts.
 pub trait Plugin {
@@ -18,9 +22,114 @@ pub trait Plugin {
     ///not an associated constant.
     fn name(&self) -> &str;
     ///The "heart" of the plugin; this is called with the syntax `plugin.<name>`.
-    fn call(&self, _state: *mut ShellState) -> Result<(), Box<dyn Error + Send + Sync>> {
+    fn call(&self, _ctx: &mut PluginContext) -> Result<(), Box<dyn Error + Send + Sync>> {
         Ok(())
     }
+    ///Called once, right after the plugin is loaded. This is the place to register hotkeys,
+    ///spawn background threads, or open files the plugin needs for its lifetime.
+    fn load(&self, _state: *mut ShellState) {}
+    ///Called once, right before the plugin is unloaded. Use this to tear down anything set up in
+    ///[`Plugin::load`]. Runs both for an explicit [`ShellState::unload_plugin`]/`plugin_rm` and
+    ///for a plugin still loaded when [`ShellState`] itself is dropped at shutdown. Still not
+    ///guaranteed to run if the process is killed or aborts.
+    fn unload(&self, _state: *mut ShellState) {}
+}
+
+///Generates the `#[no_mangle] fn init` that [`PluginHook::new`] looks up, so plugin authors don't
+///have to hand-write the `no_mangle` export themselves. Takes the plugin type and an expression
+///constructing an instance of it.
+///
+///Deliberately not `extern "C"`: `PluginHook::new` looks the symbol up as a plain Rust
+///`fn() -> Box<dyn Plugin>`, and `Box<dyn Plugin>` (a fat pointer) isn't FFI-safe to begin with,
+///so declaring the export `extern "C"` would both mismatch the loader's calling convention and
+///trip `improper_ctypes_definitions`. This only works because both sides of the boundary are
+///built with the same compiler and `Plugin` vtable layout, which is the whole reason plugins are
+///loaded as same-version `cdylib`s rather than treated as a real C ABI.
+///```
+///use zula_core::{declare_plugin, Plugin};
+///
+///pub struct MyPlugin;
+///
+///impl Plugin for MyPlugin {
+///    fn init(&self) -> Box<dyn Plugin> {
+///        Box::new(Self)
+///    }
+///    fn name(&self) -> &str {
+///        "my_plugin"
+///    }
+///}
+///
+///declare_plugin!(MyPlugin, MyPlugin);
+///```
+#[macro_export]
+macro_rules! declare_plugin {
+    ($plugin_ty:ty, $constructor:expr) => {
+        #[no_mangle]
+        pub fn init() -> Box<dyn $crate::Plugin> {
+            let object: $plugin_ty = $constructor;
+            Box::new(object)
+        }
+    };
+}
+
+///A safe handle to the [`ShellState`] passed to [`Plugin::call`]. Wraps the raw pointer the host
+///hands plugins across the abi boundary and exposes only the surface plugins actually need, so
+///the unsafe deref happens once here instead of inside every plugin.
+#[repr(C)]
+pub struct PluginContext<'a> {
+    state: &'a mut ShellState,
+}
+
+impl<'a> PluginContext<'a> {
+    ///Builds a context from the raw pointer the host passes across the abi boundary. This is the
+    ///one audited unsafe deref; everything else on `PluginContext` is safe.
+    pub(crate) unsafe fn new(state: *mut ShellState) -> Self {
+        Self { state: &mut *state }
+    }
+
+    ///Get the current working directory of the shell.
+    pub fn cwd(&self) -> &str {
+        self.state.get_cwd()
+    }
+    ///Set the current working directory of the shell. Will error if the path is not found.
+    pub fn set_cwd(&mut self, path: &str) -> Result<(), ZulaError> {
+        self.state.set_cwd(path)
+    }
+    ///Returns the shell's command history.
+    pub fn history(&self) -> &[String] {
+        &self.state.history
+    }
+    ///Returns the configured command aliases.
+    pub fn aliases(&self) -> &HashMap<String, String> {
+        &self.state.config.aliases
+    }
+    ///Returns the configured hotkeys.
+    pub fn hotkeys(&self) -> &HashMap<char, String> {
+        &self.state.config.hotkeys
+    }
+    ///Execute a command, the same as [`ShellState::exec`].
+    pub fn exec(
+        &mut self,
+        cmd: impl AsRef<str>,
+        args: &[impl AsRef<str>],
+    ) -> Result<ExitStatus, ZulaError> {
+        self.state.exec(cmd, args)
+    }
+    ///Returns a hook to the given plugin if it exists.
+    pub fn plugin_lookup(&mut self, name: &str) -> Result<&PluginHook, ZulaError> {
+        self.state.plugin_lookup(name)
+    }
+    ///Returns the shell's stdin. Guarded behind an explicit method rather than a public field,
+    ///since handing it out directly would let a plugin fight the host over raw-mode terminal
+    ///input.
+    pub fn stdin(&mut self) -> &mut Stdin {
+        &mut self.state.stdin
+    }
+    ///Returns the shell's raw-mode stdout. Guarded behind an explicit method for the same reason
+    ///as [`PluginContext::stdin`].
+    pub fn stdout(&mut self) -> &mut RawTerminal<Stdout> {
+        &mut self.state.stdout
+    }
 }
 
 ///Represents a plugin object. Not very useful outside of internal functions.
@@ -39,13 +148,27 @@ impl Deref for PluginHook {
 }
 
 impl PluginHook {
-    pub unsafe fn new<S: AsRef<OsStr>>(path: S) -> Result<Self, libloading::Error> {
+    ///Returns the path this plugin was loaded from.
+    pub(crate) fn path(&self) -> &str {
+        &self.path
+    }
+
+    ///Loads a plugin from `path` and calls its [`Plugin::load`] hook with `state`. `state` is
+    ///used only for this one call, not stashed: `ShellState` is a movable, `#[repr(C)]` value, so
+    ///holding onto a pointer derived from it past the call that produced it would dangle the
+    ///moment the shell moves. The matching [`Plugin::unload`] hook is instead called explicitly by
+    ///[`ShellState::unload_plugin`], which has a live `&mut ShellState` to take the pointer from.
+    pub unsafe fn new<S: AsRef<OsStr>>(
+        path: S,
+        state: *mut ShellState,
+    ) -> Result<Self, libloading::Error> {
         let str_path = OsStr::new(&path)
             .to_str()
             .map(|s| s.to_owned())
             .unwrap_or("".to_owned());
         let hook = Library::new(path)?;
         let obj = hook.get::<libloading::Symbol<fn() -> Box<dyn Plugin>>>(b"init")?();
+        obj.load(state);
         Ok(Self {
             hook,
             obj: ManuallyDrop::new(obj),
@@ -66,6 +189,12 @@ mod tests {
 
     #[test]
     fn drop() {
-        let hook = unsafe { PluginHook::new("/home/jamie/.config/zula/plugins/libtest_plugin.so") }.unwrap();
+        let hook = unsafe {
+            PluginHook::new(
+                "/home/jamie/.config/zula/plugins/libtest_plugin.so",
+                std::ptr::null_mut(),
+            )
+        }
+        .unwrap();
     }
 }
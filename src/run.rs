@@ -0,0 +1,238 @@
+use std::collections::HashSet;
+
+use crate::{Config, ShellState, ZulaError};
+
+///The operator that chains one command to the next.
+enum ChainOp {
+    ///`;`: always run the next command.
+    Seq,
+    ///`&&`: only run the next command if this one succeeded.
+    And,
+    ///`||`: only run the next command if this one failed.
+    Or,
+}
+
+impl ShellState {
+    ///Runs a raw input line: expands aliases, splits chained commands on `;`, `&&` and `||`,
+    ///and dispatches each one to [`ShellState::exec`]. `&&` and `||` short-circuit on the exit
+    ///status of the previous command, matching shell semantics.
+    pub fn run(&mut self, line: &str) -> Result<(), ZulaError> {
+        let (commands, ops) = group_chain(tokenize(line));
+
+        let mut last_success = true;
+        for (i, cmd) in commands.into_iter().enumerate() {
+            if cmd.is_empty() {
+                continue;
+            }
+            if i > 0 {
+                let run_next = match ops[i - 1] {
+                    ChainOp::Seq => true,
+                    ChainOp::And => last_success,
+                    ChainOp::Or => !last_success,
+                };
+                if !run_next {
+                    continue;
+                }
+            }
+
+            let resolved = resolve_alias(&self.config, &cmd)?;
+            let Some((prog, args)) = resolved.split_first() else {
+                continue;
+            };
+            last_success = match self.exec(prog, args) {
+                Ok(status) => status.success(),
+                // Command-not-found is a failed command, not a fatal error: let `;`/`&&`/`||`
+                // chaining decide whether to keep going, the same as a real shell would.
+                Err(ZulaError::InvalidCmd(_)) => false,
+                Err(e) => return Err(e),
+            };
+        }
+        Ok(())
+    }
+}
+
+///Expands aliases for the first token of `cmd`, following chained aliases until one resolves to
+///a name that isn't itself an alias. Returns [`ZulaError::RecursiveAlias`] if an alias expands
+///back to a name already seen while resolving this command.
+fn resolve_alias(config: &Config, cmd: &[String]) -> Result<Vec<String>, ZulaError> {
+    let Some(first) = cmd.first() else {
+        return Ok(cmd.to_vec());
+    };
+
+    let mut visited = HashSet::new();
+    let mut name = first.clone();
+    let mut rest = cmd[1..].to_vec();
+
+    while let Some(expansion) = config.aliases.get(&name) {
+        if !visited.insert(name.clone()) {
+            return Err(ZulaError::RecursiveAlias);
+        }
+
+        let mut expanded = tokenize(expansion);
+        if expanded.is_empty() {
+            break;
+        }
+        let new_name = expanded.remove(0);
+        expanded.extend(rest);
+        rest = expanded;
+        name = new_name;
+    }
+
+    let mut resolved = vec![name];
+    resolved.extend(rest);
+    Ok(resolved)
+}
+
+///Groups a flat token stream produced by [`tokenize`] into one argument vector per chained
+///command, alongside the operator that separates each command from the next.
+fn group_chain(tokens: Vec<String>) -> (Vec<Vec<String>>, Vec<ChainOp>) {
+    let mut commands = Vec::new();
+    let mut ops = Vec::new();
+    let mut cur = Vec::new();
+
+    for tok in tokens {
+        match tok.as_str() {
+            ";" => {
+                commands.push(std::mem::take(&mut cur));
+                ops.push(ChainOp::Seq);
+            }
+            "&&" => {
+                commands.push(std::mem::take(&mut cur));
+                ops.push(ChainOp::And);
+            }
+            "||" => {
+                commands.push(std::mem::take(&mut cur));
+                ops.push(ChainOp::Or);
+            }
+            _ => cur.push(tok),
+        }
+    }
+    commands.push(cur);
+
+    (commands, ops)
+}
+
+///Splits a raw input line into words, honoring single/double quotes and backslash escapes.
+///`;`, `&&` and `||` are emitted as standalone tokens when they appear unquoted so
+///[`group_chain`] can split on them.
+fn tokenize(line: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut cur = String::new();
+    let mut in_word = false;
+
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                if let Some(next) = chars.next() {
+                    cur.push(next);
+                    in_word = true;
+                }
+            }
+            '\'' => {
+                in_word = true;
+                for n in chars.by_ref() {
+                    if n == '\'' {
+                        break;
+                    }
+                    cur.push(n);
+                }
+            }
+            '"' => {
+                in_word = true;
+                while let Some(n) = chars.next() {
+                    if n == '"' {
+                        break;
+                    }
+                    if n == '\\' {
+                        if let Some(esc) = chars.next() {
+                            cur.push(esc);
+                        }
+                    } else {
+                        cur.push(n);
+                    }
+                }
+            }
+            ';' => {
+                if in_word {
+                    tokens.push(std::mem::take(&mut cur));
+                    in_word = false;
+                }
+                tokens.push(";".to_owned());
+            }
+            '&' if chars.peek() == Some(&'&') => {
+                chars.next();
+                if in_word {
+                    tokens.push(std::mem::take(&mut cur));
+                    in_word = false;
+                }
+                tokens.push("&&".to_owned());
+            }
+            '|' if chars.peek() == Some(&'|') => {
+                chars.next();
+                if in_word {
+                    tokens.push(std::mem::take(&mut cur));
+                    in_word = false;
+                }
+                tokens.push("||".to_owned());
+            }
+            c if c.is_whitespace() => {
+                if in_word {
+                    tokens.push(std::mem::take(&mut cur));
+                    in_word = false;
+                }
+            }
+            c => {
+                cur.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if in_word {
+        tokens.push(cur);
+    }
+
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokenize_quotes_and_escapes() {
+        let tokens = tokenize(r#"echo "hello world" 'single quoted' escaped\ space"#);
+        assert_eq!(
+            tokens,
+            vec!["echo", "hello world", "single quoted", "escaped space"]
+        );
+    }
+
+    #[test]
+    fn tokenize_operators() {
+        let tokens = tokenize("foo; bar && baz || qux");
+        assert_eq!(
+            tokens,
+            vec!["foo", ";", "bar", "&&", "baz", "||", "qux"]
+        );
+    }
+
+    #[test]
+    fn group_chain_splits_commands() {
+        let (commands, ops) = group_chain(tokenize("foo; bar && baz"));
+        assert_eq!(commands, vec![vec!["foo"], vec!["bar"], vec!["baz"]]);
+        assert_eq!(ops.len(), 2);
+        assert!(matches!(ops[0], ChainOp::Seq));
+        assert!(matches!(ops[1], ChainOp::And));
+    }
+
+    #[test]
+    fn resolve_alias_detects_cycles() {
+        let mut config = Config::new();
+        config.aliases.insert("a".to_owned(), "b".to_owned());
+        config.aliases.insert("b".to_owned(), "a".to_owned());
+
+        let err = resolve_alias(&config, &["a".to_owned()]).unwrap_err();
+        assert!(matches!(err, ZulaError::RecursiveAlias));
+    }
+}